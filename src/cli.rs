@@ -0,0 +1,49 @@
+use crate::Baselines;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum OutputFormat {
+    Html,
+    Markdown,
+    Json,
+    Oscal,
+}
+
+/// Compares FedRAMP's High/Moderate/Low security control baselines.
+#[derive(Debug, Parser)]
+pub struct Cli {
+    /// Read the baseline workbook from this local .xlsx file instead of
+    /// downloading it from fedramp.gov.
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+
+    /// Baselines to load and compare. Defaults to all three.
+    #[arg(long = "baseline", value_enum)]
+    pub baselines: Vec<Baselines>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "html")]
+    pub format: OutputFormat,
+
+    /// Write output to this file instead of stdout.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Diff against a previous revision of the baseline workbook (a local
+    /// .xlsx file) and, for HTML output, add a "Changes" tab highlighting
+    /// added, removed, and modified controls.
+    #[arg(long)]
+    pub diff_against: Option<PathBuf>,
+}
+
+impl Cli {
+    pub fn selected_baselines(&self) -> Vec<Baselines> {
+        if self.baselines.is_empty() {
+            use strum::IntoEnumIterator;
+            Baselines::iter().collect()
+        } else {
+            self.baselines.clone()
+        }
+    }
+}
@@ -13,6 +13,31 @@ impl ControlID {
     pub fn is_empty(&self) -> bool {
         return self.subject.is_empty() || self.number == 0;
     }
+
+    /// Renders the ID in the lowercase `subject-number.subnumber` form used
+    /// by OSCAL catalogs and profiles, e.g. `ac-2.1`.
+    pub fn oscal_id(&self) -> String {
+        if self.subnumber > 0 {
+            format!("{}-{}.{}", self.subject.to_lowercase(), self.number, self.subnumber)
+        } else {
+            format!("{}-{}", self.subject.to_lowercase(), self.number)
+        }
+    }
+
+    /// Whether this ID names a control enhancement, e.g. `AC-2 (1)`.
+    pub fn is_enhancement(&self) -> bool {
+        self.subnumber > 0
+    }
+
+    /// The base control that owns this enhancement, e.g. `AC-2 (1)` -> `AC-2`.
+    /// Returns a copy of `self` if this ID is already a base control.
+    pub fn base(&self) -> ControlID {
+        ControlID {
+            subject: self.subject.clone(),
+            number: self.number,
+            subnumber: 0,
+        }
+    }
 }
 
 impl fmt::Display for ControlID {
@@ -25,6 +50,15 @@ impl fmt::Display for ControlID {
     }
 }
 
+impl serde::Serialize for ControlID {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseControlIDErr;
 
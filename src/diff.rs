@@ -0,0 +1,192 @@
+use crate::control_id::ControlID;
+use crate::{Baselines, Control, ControlStatus, Controls, Parameters};
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    Name { old: String, new: String },
+    Description { old: String, new: String },
+    Discussion { old: String, new: String },
+    Status { old: ControlStatus, new: ControlStatus },
+    Parameters {
+        baseline: Baselines,
+        old: Option<Parameters>,
+        new: Option<Parameters>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ControlDiff {
+    pub id: ControlID,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub added: Vec<Control>,
+    pub removed: Vec<Control>,
+    pub changed: Vec<ControlDiff>,
+}
+
+fn field_changes(old: &Control, new: &Control) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+
+    if old.name != new.name {
+        changes.push(FieldChange::Name {
+            old: old.name.clone(),
+            new: new.name.clone(),
+        });
+    }
+    if old.description != new.description {
+        changes.push(FieldChange::Description {
+            old: old.description.clone(),
+            new: new.description.clone(),
+        });
+    }
+    if old.discussion != new.discussion {
+        changes.push(FieldChange::Discussion {
+            old: old.discussion.clone(),
+            new: new.discussion.clone(),
+        });
+    }
+    if old.status != new.status {
+        changes.push(FieldChange::Status {
+            old: old.status.clone(),
+            new: new.status.clone(),
+        });
+    }
+    for baseline in Baselines::iter() {
+        let old_parameters = old.parameters[baseline].clone().map(|p| p.flatten());
+        let new_parameters = new.parameters[baseline].clone().map(|p| p.flatten());
+        if old_parameters != new_parameters {
+            changes.push(FieldChange::Parameters {
+                baseline,
+                old: old_parameters,
+                new: new_parameters,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Renders both halves of a parameter value so a diff that only touches
+/// `additional` (or only `assignment`) is still visible in the Old/New cell.
+fn format_parameters(parameters: &Option<Parameters>) -> String {
+    match parameters {
+        None => String::new(),
+        Some(parameters) => format!(
+            "assignment: {}<br>additional: {}",
+            parameters.assignment, parameters.additional
+        ),
+    }
+}
+
+pub fn render_html(diff: &Diff) -> String {
+    use build_html::{Container, Html, HtmlContainer, Table, TableCell, TableCellType, TableRow};
+
+    let mut container = Container::default();
+
+    let added_or_removed_table = |controls: &[Control]| {
+        let mut controls: Vec<&Control> = controls.iter().collect();
+        controls.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut table = Table::new().with_custom_header_row(
+            TableRow::new()
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("ID"))
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("Name")),
+        );
+        for control in controls {
+            table.add_custom_body_row(
+                TableRow::new()
+                    .with_cell(TableCell::default().with_raw(control.id.to_string()))
+                    .with_cell(TableCell::default().with_raw(control.name.as_str())),
+            );
+        }
+        table
+    };
+
+    if !diff.added.is_empty() {
+        container.add_raw("<h3>Added controls</h3>");
+        container = container.with_table(added_or_removed_table(&diff.added));
+    }
+
+    if !diff.removed.is_empty() {
+        container.add_raw("<h3>Removed controls</h3>");
+        container = container.with_table(added_or_removed_table(&diff.removed));
+    }
+
+    if !diff.changed.is_empty() {
+        let mut changed: Vec<&ControlDiff> = diff.changed.iter().collect();
+        changed.sort_by(|a, b| a.id.cmp(&b.id));
+        container.add_raw("<h3>Changed controls</h3>");
+        let mut table = Table::new().with_custom_header_row(
+            TableRow::new()
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("ID"))
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("Field"))
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("Old"))
+                .with_cell(TableCell::new(TableCellType::Header).with_raw("New")),
+        );
+        for control_diff in changed {
+            for change in &control_diff.changes {
+                let (field, old, new) = match change {
+                    FieldChange::Name { old, new } => ("Name".to_string(), old.clone(), new.clone()),
+                    FieldChange::Description { old, new } => {
+                        ("Description".to_string(), old.clone(), new.clone())
+                    }
+                    FieldChange::Discussion { old, new } => {
+                        ("Discussion".to_string(), old.clone(), new.clone())
+                    }
+                    FieldChange::Status { old, new } => {
+                        ("Status".to_string(), old.describe(), new.describe())
+                    }
+                    FieldChange::Parameters { baseline, old, new } => (
+                        format!("Parameters ({})", baseline.short()),
+                        format_parameters(old),
+                        format_parameters(new),
+                    ),
+                };
+                table.add_custom_body_row(
+                    TableRow::new()
+                        .with_cell(TableCell::default().with_raw(control_diff.id.to_string()))
+                        .with_cell(TableCell::default().with_raw(field))
+                        .with_cell(TableCell::default().with_raw(old))
+                        .with_cell(TableCell::default().with_raw(new)),
+                );
+            }
+        }
+        container = container.with_table(table);
+    }
+
+    container.to_html_string()
+}
+
+/// Compares two merged `Controls` snapshots (e.g. a previous and current
+/// revision of the FedRAMP workbook), keyed by `ControlID`. Parameter
+/// changes are compared after `Parameters::flatten()` so whitespace
+/// reformatting in the workbook doesn't show up as a change.
+pub fn diff(old: &Controls, new: &Controls) -> Diff {
+    let mut result = Diff::default();
+
+    for (id, new_control) in &new.controls {
+        match old.controls.get(id) {
+            None => result.added.push(new_control.clone()),
+            Some(old_control) => {
+                let changes = field_changes(old_control, new_control);
+                if !changes.is_empty() {
+                    result.changed.push(ControlDiff {
+                        id: id.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+
+    for (id, old_control) in &old.controls {
+        if !new.controls.contains_key(id) {
+            result.removed.push(old_control.clone());
+        }
+    }
+
+    result
+}
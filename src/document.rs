@@ -0,0 +1,282 @@
+use crate::control_id::ControlID;
+use crate::{Baselines, Controls};
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+pub const HEADER: [&str; 10] = [
+    "ID",
+    "H",
+    "M",
+    "L",
+    "Name",
+    "Description",
+    "Discussion",
+    "Level",
+    "Assignment",
+    "Additional guidance",
+];
+
+/// A sub-row listing one baseline's parameters, used when a control's
+/// parameters differ between baselines. `parameters` is `None` when the
+/// control isn't present in that baseline at all.
+#[derive(Debug, Clone)]
+pub struct ParameterRow {
+    pub baseline: Baselines,
+    pub parameters: Option<(String, String)>,
+}
+
+/// One control, ready to render. When `parameter_rows` is non-empty the
+/// control's parameters differ per baseline and should be rendered as
+/// sub-rows; otherwise `shared_parameters` holds the single value common
+/// to every baseline the control appears in.
+#[derive(Debug, Clone)]
+pub struct ControlRow {
+    pub id: String,
+    pub high: bool,
+    pub moderate: bool,
+    pub low: bool,
+    pub name: String,
+    pub description: String,
+    pub discussion: String,
+    pub shared_parameters: Option<(String, String)>,
+    pub parameter_rows: Vec<ParameterRow>,
+    /// True for control enhancements (e.g. `AC-2 (1)`), which sort directly
+    /// under their base control (`AC-2`) and can be styled accordingly.
+    pub is_enhancement: bool,
+    /// The enhancements owned by this control (e.g. `AC-2` -> `["AC-2 (1)", "AC-2 (2)"]`),
+    /// recorded via `ControlID::base` so the table can group them under it.
+    /// Always empty for a control that is itself an enhancement.
+    pub enhancements: Vec<String>,
+    /// Set for withdrawn controls, e.g. `Withdrawn: incorporated into AC-2`.
+    pub status_note: Option<String>,
+}
+
+/// Groups every enhancement's ID under its base control's ID, using
+/// `ControlID::base` to recover the parent relationship.
+fn enhancements_by_base(controls: &Controls) -> HashMap<ControlID, Vec<ControlID>> {
+    let mut groups: HashMap<ControlID, Vec<ControlID>> = HashMap::new();
+    for id in controls.controls.keys().filter(|id| id.is_enhancement()) {
+        groups.entry(id.base()).or_default().push(id.clone());
+    }
+    for ids in groups.values_mut() {
+        ids.sort();
+    }
+    groups
+}
+
+/// The renderer-agnostic intermediate representation of a controls table:
+/// a header plus one row per control, with the rowspan/parameter-expansion
+/// logic already resolved so every `Renderer` produces consistent output.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub header: Vec<&'static str>,
+    pub rows: Vec<ControlRow>,
+}
+
+impl Controls {
+    pub fn to_document(&self) -> Document {
+        let mut ids: Vec<&ControlID> = self.controls.keys().collect();
+        ids.sort();
+
+        let enhancement_groups = enhancements_by_base(self);
+
+        let rows = ids
+            .into_iter()
+            .map(|id| {
+                let control = &self.controls[id];
+                let has_parameter_rows = control.distinct_parameters();
+
+                let shared_parameters = if has_parameter_rows {
+                    None
+                } else {
+                    let parameters = control
+                        .parameters
+                        .values()
+                        .find_map(|p| p.clone())
+                        .unwrap_or_default();
+                    Some((parameters.assignment, parameters.additional))
+                };
+
+                let parameter_rows = if has_parameter_rows {
+                    Baselines::iter()
+                        .map(|baseline| ParameterRow {
+                            baseline,
+                            parameters: control.parameters[baseline]
+                                .clone()
+                                .map(|p| (p.assignment, p.additional)),
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+
+                let status_note = match &control.status {
+                    crate::ControlStatus::Active => None,
+                    status => Some(status.describe()),
+                };
+
+                let enhancements = enhancement_groups
+                    .get(id)
+                    .map(|ids| ids.iter().map(|id| id.to_string()).collect())
+                    .unwrap_or_default();
+
+                ControlRow {
+                    id: id.to_string(),
+                    high: control.parameters[Baselines::High].is_some(),
+                    moderate: control.parameters[Baselines::Moderate].is_some(),
+                    low: control.parameters[Baselines::Low].is_some(),
+                    name: control.name.clone(),
+                    description: control.description.clone(),
+                    discussion: control.discussion.clone(),
+                    shared_parameters,
+                    parameter_rows,
+                    is_enhancement: id.is_enhancement(),
+                    enhancements,
+                    status_note,
+                }
+            })
+            .collect();
+
+        Document {
+            header: HEADER.to_vec(),
+            rows,
+        }
+    }
+}
+
+pub trait Renderer {
+    fn render(&self, document: &Document) -> String;
+}
+
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, document: &Document) -> String {
+        use build_html::{Container, Html, HtmlContainer, Table, TableCell, TableCellType, TableRow};
+
+        let mut table = Table::new().with_custom_header_row(document.header.iter().fold(
+            TableRow::new(),
+            |row, name| row.with_cell(TableCell::new(TableCellType::Header).with_raw(*name)),
+        ));
+
+        for row in &document.rows {
+            let rowspan = if row.parameter_rows.is_empty() {
+                1
+            } else {
+                1 + row.parameter_rows.len()
+            }
+            .to_string();
+
+            let shared_cell = |content: &str| {
+                TableCell::new(TableCellType::Data)
+                    .with_raw(content)
+                    .with_attributes([("rowspan", rowspan.as_str())])
+            };
+
+            let tick = |present: bool| if present { "\u{2713}" } else { "" };
+            let mut name = row.name.replace(" | ", "\n");
+            if let Some(note) = &row.status_note {
+                name = format!("{} [{}]", name, note);
+            }
+            if !row.enhancements.is_empty() {
+                name = format!("{} (enhancements: {})", name, row.enhancements.join(", "));
+            }
+
+            let class = if row.is_enhancement { "shared enhancement" } else { "shared" };
+            let mut table_row = TableRow::new()
+                .with_attributes([("class", class)])
+                .with_cell(shared_cell(row.id.as_str()))
+                .with_cell(shared_cell(tick(row.high)))
+                .with_cell(shared_cell(tick(row.moderate)))
+                .with_cell(shared_cell(tick(row.low)))
+                .with_cell(shared_cell(name.as_str()))
+                .with_cell(shared_cell(row.description.as_str()))
+                .with_cell(shared_cell(row.discussion.as_str()));
+
+            if let Some((assignment, additional)) = &row.shared_parameters {
+                table_row = table_row
+                    .with_cell(shared_cell(""))
+                    .with_cell(shared_cell(assignment.as_str()))
+                    .with_cell(shared_cell(additional.as_str()));
+            }
+
+            table.add_custom_body_row(table_row);
+
+            for parameter_row in &row.parameter_rows {
+                let mut table_row = TableRow::new().with_attributes([(
+                    "class",
+                    format!("parameters {}", parameter_row.baseline.short()).as_str(),
+                )]);
+                if let Some((assignment, additional)) = &parameter_row.parameters {
+                    table_row = table_row
+                        .with_cell(TableCell::default().with_raw(parameter_row.baseline.short()))
+                        .with_cell(TableCell::default().with_raw(assignment.as_str()))
+                        .with_cell(TableCell::default().with_raw(additional.as_str()));
+                }
+                table.add_custom_body_row(table_row);
+            }
+        }
+
+        Container::default().with_table(table).to_html_string()
+    }
+}
+
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, document: &Document) -> String {
+        let escape = |s: &str| s.replace('|', "\\|").replace('\n', "<br>");
+
+        let mut out = String::new();
+        out.push_str("| ");
+        out.push_str(&document.header.join(" | "));
+        out.push_str(" |\n");
+        out.push_str("| ");
+        out.push_str(&document.header.iter().map(|_| "---").collect::<Vec<_>>().join(" | "));
+        out.push_str(" |\n");
+
+        let tick = |present: bool| if present { "\u{2713}" } else { "" };
+
+        for row in &document.rows {
+            let (level, assignment, additional) = match &row.shared_parameters {
+                Some((assignment, additional)) => ("", assignment.as_str(), additional.as_str()),
+                None => ("", "", ""),
+            };
+            let mut name = match &row.status_note {
+                Some(note) => format!("{} [{}]", row.name, note),
+                None => row.name.clone(),
+            };
+            if !row.enhancements.is_empty() {
+                name = format!("{} (enhancements: {})", name, row.enhancements.join(", "));
+            }
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                escape(&row.id),
+                tick(row.high),
+                tick(row.moderate),
+                tick(row.low),
+                escape(&name),
+                escape(&row.description),
+                escape(&row.discussion),
+                level,
+                escape(assignment),
+                escape(additional),
+            ));
+
+            for parameter_row in &row.parameter_rows {
+                let (assignment, additional) = match &parameter_row.parameters {
+                    Some((assignment, additional)) => (assignment.as_str(), additional.as_str()),
+                    None => ("", ""),
+                };
+                out.push_str(&format!(
+                    "| | | | | | | | {} | {} | {} |\n",
+                    parameter_row.baseline.short(),
+                    escape(assignment),
+                    escape(additional),
+                ));
+            }
+        }
+
+        out
+    }
+}
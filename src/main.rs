@@ -1,5 +1,6 @@
 use build_html::*;
 use calamine::{DataType, Reader, Xlsx};
+use clap::Parser;
 use enum_map::{Enum, EnumMap};
 use lazy_regex::regex;
 use std::collections::{HashMap, HashSet};
@@ -9,7 +10,17 @@ use strum::{EnumIter, IntoEnumIterator};
 mod control_id;
 use control_id::ControlID;
 
-#[derive(Debug, Enum, Clone, Copy, EnumIter, PartialEq, Eq, Hash)]
+mod cli;
+use cli::{Cli, OutputFormat};
+
+mod diff;
+
+mod document;
+use document::{HtmlRenderer, MarkdownRenderer, Renderer};
+
+mod oscal;
+
+#[derive(Debug, Enum, Clone, Copy, EnumIter, PartialEq, Eq, Hash, clap::ValueEnum, serde::Serialize)]
 enum Baselines {
     High,
     Moderate,
@@ -34,7 +45,7 @@ impl Baselines {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialOrd, Ord, PartialEq, Eq, serde::Serialize)]
 struct Parameters {
     assignment: String,
     additional: String,
@@ -51,12 +62,58 @@ impl Parameters {
     }
 }
 
+/// Whether a control is still active in the baseline, or has been withdrawn
+/// and folded into one or more other controls (NIST periodically retires
+/// controls this way between revisions).
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize)]
+enum ControlStatus {
+    #[default]
+    Active,
+    Withdrawn { incorporated_into: Vec<ControlID> },
+}
+
+/// Parses a "Control Name" cell, splitting out a withdrawal annotation like
+/// `Withdrawn: Incorporated into AC-2, AC-3` into a `ControlStatus` and
+/// returning the status alongside the name with that annotation stripped.
+fn parse_control_status(name: &str) -> (String, ControlStatus) {
+    if !name.to_lowercase().contains("withdrawn") {
+        return (name.to_string(), ControlStatus::Active);
+    }
+
+    let id_pattern = regex!(r"[A-Za-z]+-\d+(?:\s*\(\d+\))?");
+    let incorporated_into = id_pattern
+        .find_iter(name)
+        .filter_map(|m| m.as_str().parse::<ControlID>().ok())
+        .collect();
+
+    let withdrawn_pattern = regex!(r"(?i)\[?withdrawn[^\]]*\]?");
+    let stripped = withdrawn_pattern.replace_all(name, "").trim().to_string();
+
+    (stripped, ControlStatus::Withdrawn { incorporated_into })
+}
+
+impl ControlStatus {
+    fn describe(&self) -> String {
+        match self {
+            ControlStatus::Active => "Active".to_string(),
+            ControlStatus::Withdrawn { incorporated_into } if incorporated_into.is_empty() => {
+                "Withdrawn".to_string()
+            }
+            ControlStatus::Withdrawn { incorporated_into } => format!(
+                "Withdrawn: incorporated into {}",
+                incorporated_into.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Control {
     id: ControlID,
     name: String,
     description: String,
     discussion: String,
+    status: ControlStatus,
     parameters: EnumMap<Baselines, Option<Parameters>>,
 }
 impl Control {
@@ -96,6 +153,7 @@ impl Clone for Control {
             name: self.name.clone(),
             description: self.description.clone(),
             discussion: self.discussion.clone(),
+            status: self.status.clone(),
             parameters: EnumMap::from_array(self.parameters.as_array().clone()),
         }
     }
@@ -108,12 +166,37 @@ impl Default for Control {
             name: "".into(),
             description: "".into(),
             discussion: "".into(),
+            status: ControlStatus::default(),
             parameters: EnumMap::from_fn(|_| None),
         };
     }
 }
 
-#[derive(Debug, Default)]
+// `EnumMap`'s `Serialize` impl is gated behind its optional `serde` feature,
+// which this crate doesn't enable, so `parameters` is serialized by hand as a
+// plain map keyed by baseline name instead of deriving through the `EnumMap`.
+impl serde::Serialize for Control {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let parameters: HashMap<&str, &Option<Parameters>> = Baselines::iter()
+            .map(|baseline| (baseline.short(), &self.parameters[baseline]))
+            .collect();
+
+        let mut state = serializer.serialize_struct("Control", 6)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("discussion", &self.discussion)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("parameters", &parameters)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize)]
 struct Controls {
     controls: HashMap<ControlID, Control>,
 }
@@ -142,7 +225,11 @@ impl Controls {
                                     control.id = id
                                 }
                             }
-                            "Control Name" => control.name = value.trim().to_string(),
+                            "Control Name" => {
+                                let (name, status) = parse_control_status(value.trim());
+                                control.name = name;
+                                control.status = status;
+                            }
                             s if s.starts_with("NIST Control Description") => {
                                 control.description = value.trim().to_string()
                             }
@@ -175,16 +262,26 @@ impl Controls {
     }
 }
 
-async fn get_baselines() -> Result<HashMap<Baselines, Controls>, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let content = client.get("https://fedramp.gov/assets/resources/documents/FedRAMP_Security_Controls_Baseline.xlsx")
-        .send().await?.bytes().await?;
+const FEDRAMP_XLSX_URL: &str =
+    "https://fedramp.gov/assets/resources/documents/FedRAMP_Security_Controls_Baseline.xlsx";
+
+async fn get_baselines(
+    input: Option<&std::path::Path>,
+    wanted: &[Baselines],
+) -> Result<HashMap<Baselines, Controls>, Box<dyn std::error::Error>> {
+    let content = match input {
+        Some(path) => std::fs::read(path)?,
+        None => {
+            let client = reqwest::Client::new();
+            client.get(FEDRAMP_XLSX_URL).send().await?.bytes().await?.to_vec()
+        }
+    };
     let buf = Cursor::new(content);
     let mut wb: Xlsx<_> = calamine::open_workbook_from_rs(buf)?;
     let mut baselines = HashMap::new();
-    for baseline in Baselines::iter() {
+    for baseline in wanted {
         if let Ok(sheet) = wb.worksheet_range(baseline.as_str()) {
-            baselines.insert(baseline, Controls::parse(sheet, baseline));
+            baselines.insert(*baseline, Controls::parse(sheet, *baseline));
         }
     }
     return Ok(baselines);
@@ -202,13 +299,20 @@ fn merge_controls(baselines: HashMap<Baselines, Controls>) -> Controls {
     for id in all_controls {
         let mut merged = Control::default();
 
-        let high = baselines[&Baselines::High].controls.get(&id).unwrap();
-        merged.id = high.id.clone();
-        merged.name = high.name.to_string();
-        merged.description = high.description.to_string();
-        merged.discussion = high.discussion.to_string();
+        // Prefer descriptive fields from the highest baseline that actually
+        // contains the control: most controls live in every baseline, but
+        // some enhancements or withdrawn controls only appear in Moderate
+        // or Low.
+        if let Some(source) = Baselines::iter().find_map(|level| baselines.get(&level).and_then(|b| b.controls.get(&id))) {
+            merged.id = source.id.clone();
+            merged.name = source.name.to_string();
+            merged.description = source.description.to_string();
+            merged.discussion = source.discussion.to_string();
+            merged.status = source.status.clone();
+        }
+
         for level in Baselines::iter() {
-            if let Some(control) = baselines[&level].controls.get(&id) {
+            if let Some(control) = baselines.get(&level).and_then(|b| b.controls.get(&id)) {
                 merged.parameters[level] = control.parameters[level].clone();
             }
         }
@@ -221,97 +325,6 @@ fn merge_controls(baselines: HashMap<Baselines, Controls>) -> Controls {
     };
 }
 
-fn tabulate_controls(controls: &Controls) -> build_html::Table {
-    let mut ids: Vec<&ControlID> = controls.controls.keys().collect();
-    ids.sort();
-    let mut table = Table::new().with_custom_header_row(
-        TableRow::new()
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("ID"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("H"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("M"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("L"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Name"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Description"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Discussion"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Level"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Assignment"))
-            .with_cell(TableCell::new(TableCellType::Header).with_raw("Additional guidance")),
-    );
-    for id in ids {
-        let control = controls.controls.get(id).unwrap();
-
-        let tick = "\u{2713}";
-        let tick_if_present = |level| {
-            if control.parameters[level].is_some() {
-                tick
-            } else {
-                ""
-            }
-        };
-
-        let has_parameter_rows = control.distinct_parameters();
-        let rowspan = if has_parameter_rows {
-            1 + control.parameters.len()
-        } else {
-            1
-        }
-        .to_string();
-
-        let shared_cell = |content| {
-            TableCell::new(TableCellType::Data)
-                .with_raw(content)
-                .with_attributes([("rowspan", rowspan.as_str())])
-        };
-
-        let id_str = id.to_string();
-        let name_str = control.name.replace(" | ", "\n");
-        let mut row = TableRow::new()
-            .with_attributes([("class", "shared")])
-            .with_cell(shared_cell(id_str.as_str()))
-            .with_cell(shared_cell(tick_if_present(Baselines::High)))
-            .with_cell(shared_cell(tick_if_present(Baselines::Moderate)))
-            .with_cell(shared_cell(tick_if_present(Baselines::Low)))
-            .with_cell(shared_cell(name_str.as_str()))
-            .with_cell(shared_cell(control.description.as_str()))
-            .with_cell(shared_cell(control.discussion.as_str()));
-
-        if !has_parameter_rows {
-            row = row.with_cell(shared_cell(""));
-            if let Some(Some(parameters)) = control.parameters.values().next() {
-                row = row
-                    .with_cell(shared_cell(parameters.assignment.as_str()))
-                    .with_cell(shared_cell(parameters.additional.as_str()))
-            } else {
-                row = row.with_cell(shared_cell("")).with_cell(shared_cell(""));
-            }
-        }
-
-        table.add_custom_body_row(row);
-
-        if has_parameter_rows {
-            for level in Baselines::iter() {
-                let mut row = TableRow::new()
-                    .with_attributes([("class", format!("parameters {}", level.short()).as_str())]);
-                match &control.parameters[level] {
-                    Some(parameters) => {
-                        row = row
-                            .with_cell(TableCell::default().with_raw(level.short()))
-                            .with_cell(
-                                TableCell::default().with_raw(parameters.assignment.as_str()),
-                            )
-                            .with_cell(
-                                TableCell::default().with_raw(parameters.additional.as_str()),
-                            );
-                    }
-                    _ => {}
-                }
-                table.add_custom_body_row(row);
-            }
-        }
-    }
-    return table;
-}
-
 fn add_tab(html: &mut impl HtmlContainer, name: &str, title: &str, checked: bool, content: Container) {
     let input = format!(r#"<input name="tabs" type="radio" id="{name}" {} class="input"/>"#, if checked {r#"checked="checked""#} else {""});
     let label = format!(r#"<label for="{name}" class="label">{title}</label>"#);
@@ -320,18 +333,62 @@ fn add_tab(html: &mut impl HtmlContainer, name: &str, title: &str, checked: bool
     html.add_container(content.with_attributes([("class", "panel")]));
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let baselines = get_baselines().await?;
-    let controls = merge_controls(baselines);
+fn render_html(controls: &Controls, changes: Option<&diff::Diff>) -> String {
+    let renderer = HtmlRenderer;
     let mut page = build_html::HtmlPage::new()
         .with_title("fedramp controls comparison")
         .with_head_link("style.css", "stylesheet");
     let mut tabs = Container::default().with_attributes([("class", "tabs")]);
-    add_tab(&mut tabs, "all", "All controls", true, Container::default().with_table(tabulate_controls(&controls)));
-    add_tab(&mut tabs, "high-moderate", "High-Moderate", false, Container::default().with_table(tabulate_controls(&controls.without_baseline(Baselines::Low))));
-    add_tab(&mut tabs, "moderate-low", "Moderate-Low", false, Container::default().with_table(tabulate_controls(&controls.without_baseline(Baselines::High))));
+    add_tab(&mut tabs, "all", "All controls", true, Container::default().with_raw(renderer.render(&controls.to_document())));
+    add_tab(&mut tabs, "high-moderate", "High-Moderate", false, Container::default().with_raw(renderer.render(&controls.without_baseline(Baselines::Low).to_document())));
+    add_tab(&mut tabs, "moderate-low", "Moderate-Low", false, Container::default().with_raw(renderer.render(&controls.without_baseline(Baselines::High).to_document())));
+    if let Some(changes) = changes {
+        add_tab(&mut tabs, "changes", "Changes", false, Container::default().with_raw(diff::render_html(changes)));
+    }
     page = page.with_container(tabs);
-    println!("{}", page.to_html_string());
+    return page.to_html_string();
+}
+
+fn render_markdown(controls: &Controls) -> String {
+    MarkdownRenderer.render(&controls.to_document())
+}
+
+fn render_oscal(controls: &Controls) -> Result<String, Box<dyn std::error::Error>> {
+    let catalog = oscal::catalog(controls);
+    let profiles: HashMap<String, oscal::OscalProfile> = Baselines::iter()
+        .map(|baseline| (baseline.short().to_string(), oscal::profile(controls, baseline, "catalog.json")))
+        .collect();
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "catalog": catalog,
+        "profiles": profiles,
+    }))?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Cli::parse();
+    let wanted = args.selected_baselines();
+    let baselines = get_baselines(args.input.as_deref(), &wanted).await?;
+    let controls = merge_controls(baselines);
+
+    let changes = match &args.diff_against {
+        Some(path) => {
+            let old_baselines = get_baselines(Some(path), &wanted).await?;
+            Some(diff::diff(&merge_controls(old_baselines), &controls))
+        }
+        None => None,
+    };
+
+    let rendered = match args.format {
+        OutputFormat::Html => render_html(&controls, changes.as_ref()),
+        OutputFormat::Markdown => render_markdown(&controls),
+        OutputFormat::Json => serde_json::to_string_pretty(&controls)?,
+        OutputFormat::Oscal => render_oscal(&controls)?,
+    };
+
+    match args.output {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => println!("{}", rendered),
+    }
     return Ok(());
 }
@@ -0,0 +1,165 @@
+use crate::control_id::ControlID;
+use crate::{Baselines, Control, Controls};
+use serde::Serialize;
+
+const ASSIGNMENT_PARAM_SUFFIX: &str = "_prm.assignment";
+const ADDITIONAL_PARAM_SUFFIX: &str = "_prm.additional";
+
+#[derive(Debug, Serialize)]
+pub struct OscalCatalog {
+    pub controls: Vec<OscalControl>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalControl {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<OscalParam>,
+    pub parts: Vec<OscalPart>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalParam {
+    pub id: String,
+    pub label: String,
+    /// The catalog-level default value, used by every baseline unless a
+    /// profile overrides it with a `set-parameter` entry. Empty when the
+    /// control's parameters differ between baselines, so there is no single
+    /// default to carry over.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalPart {
+    pub name: String,
+    pub prose: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalProfile {
+    pub imports: Vec<OscalImport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalImport {
+    pub href: String,
+    #[serde(rename = "include-controls")]
+    pub include_controls: Vec<OscalIncludeControls>,
+    #[serde(rename = "set-parameters", skip_serializing_if = "Vec::is_empty")]
+    pub set_parameters: Vec<OscalSetParameter>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalIncludeControls {
+    #[serde(rename = "with-ids")]
+    pub with_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OscalSetParameter {
+    #[serde(rename = "param-id")]
+    pub param_id: String,
+    pub values: Vec<String>,
+}
+
+fn control_params(control: &Control) -> Vec<OscalParam> {
+    if !control.parameters.values().any(Option::is_some) {
+        return Vec::new();
+    }
+
+    // When every baseline agrees, that shared value becomes the catalog
+    // default; `profile` only adds `set-parameter` overrides for controls
+    // where baselines actually disagree.
+    let shared = if control.distinct_parameters() {
+        None
+    } else {
+        control.parameters.values().find_map(|p| p.clone())
+    };
+
+    vec![
+        OscalParam {
+            id: format!("{}{}", control.id.oscal_id(), ASSIGNMENT_PARAM_SUFFIX),
+            label: "assignment / selection".into(),
+            values: shared.as_ref().map(|p| vec![p.assignment.clone()]).unwrap_or_default(),
+        },
+        OscalParam {
+            id: format!("{}{}", control.id.oscal_id(), ADDITIONAL_PARAM_SUFFIX),
+            label: "additional FedRAMP requirements and guidance".into(),
+            values: shared.as_ref().map(|p| vec![p.additional.clone()]).unwrap_or_default(),
+        },
+    ]
+}
+
+/// Builds an OSCAL catalog containing every merged control, independent of
+/// which baselines it appears in.
+pub fn catalog(controls: &Controls) -> OscalCatalog {
+    let mut ids: Vec<&ControlID> = controls.controls.keys().collect();
+    ids.sort();
+    OscalCatalog {
+        controls: ids
+            .into_iter()
+            .map(|id| {
+                let control = &controls.controls[id];
+                OscalControl {
+                    id: control.id.oscal_id(),
+                    title: control.name.clone(),
+                    params: control_params(control),
+                    parts: vec![
+                        OscalPart {
+                            name: "statement".into(),
+                            prose: control.description.clone(),
+                        },
+                        OscalPart {
+                            name: "guidance".into(),
+                            prose: control.discussion.clone(),
+                        },
+                    ],
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Builds an OSCAL profile selecting the controls present in `baseline`,
+/// importing from a catalog at `catalog_href`. Parameter values are only
+/// emitted as `set-parameter` entries when they differ between baselines
+/// (`Control::distinct_parameters`); controls with a single shared value
+/// rely on the catalog's default.
+pub fn profile(controls: &Controls, baseline: Baselines, catalog_href: &str) -> OscalProfile {
+    let mut ids: Vec<&ControlID> = controls
+        .controls
+        .keys()
+        .filter(|id| controls.controls[*id].parameters[baseline].is_some())
+        .collect();
+    ids.sort();
+
+    let mut set_parameters = Vec::new();
+    for id in &ids {
+        let control = &controls.controls[*id];
+        if !control.distinct_parameters() {
+            continue;
+        }
+        if let Some(parameters) = &control.parameters[baseline] {
+            set_parameters.push(OscalSetParameter {
+                param_id: format!("{}{}", control.id.oscal_id(), ASSIGNMENT_PARAM_SUFFIX),
+                values: vec![parameters.assignment.clone()],
+            });
+            set_parameters.push(OscalSetParameter {
+                param_id: format!("{}{}", control.id.oscal_id(), ADDITIONAL_PARAM_SUFFIX),
+                values: vec![parameters.additional.clone()],
+            });
+        }
+    }
+
+    OscalProfile {
+        imports: vec![OscalImport {
+            href: catalog_href.to_string(),
+            include_controls: vec![OscalIncludeControls {
+                with_ids: ids.iter().map(|id| id.oscal_id()).collect(),
+            }],
+            set_parameters,
+        }],
+    }
+}